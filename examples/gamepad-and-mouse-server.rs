@@ -6,9 +6,12 @@ use std::thread;
 use std::fs;
 use std::collections::HashMap;
 
-use gilrs::{Gilrs, Button, Axis};
+use gilrs::{Gilrs, GamepadId, Button, Axis};
+use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Replay, Ticks};
 use multiinput::{RawInputManager, RawEvent};
 
+use pad_motion::button_state::ButtonState;
+use pad_motion::orientation::Orientation;
 use pad_motion::protocol::*;
 use pad_motion::server::*;
 
@@ -17,7 +20,6 @@ struct AppConfig {
     sensitivity: f32,
     invert_x: f32,      // 1.0 or -1.0
     invert_y: f32,      // 1.0 or -1.0
-    gravity_axis: u8,   // 0=X, 1=Y, 2=Z
     gravity_amount: f32 // Usually 9.81
 }
 
@@ -27,7 +29,6 @@ impl Default for AppConfig {
             sensitivity: 5.0,
             invert_x: -1.0, // Flipped based on your feedback
             invert_y: 1.0,  // Flipped based on your feedback
-            gravity_axis: 1, // 1 = Y-Axis (Upright/Remote style) to fix "X" movement
             gravity_amount: 9.81,
         }
     }
@@ -49,13 +50,7 @@ fn main() {
     server.start(running.clone())
   };
 
-  let controller_info = ControllerInfo {
-    slot_state: SlotState::Connected,
-    device_type: DeviceType::FullGyro,
-    connection_type: ConnectionType::USB,
-    .. Default::default()
-  };
-  server.update_controller_info(controller_info);
+  let mut controller_registry = ControllerRegistry::new();
 
   fn to_stick_value(input: f32) -> u8 {
     (input * 127.0 + 127.0) as u8 
@@ -86,7 +81,6 @@ fn main() {
                               "sensitivity" => new_config.sensitivity = val,
                               "invert_x" => new_config.invert_x = if val > 0.0 { 1.0 } else { -1.0 },
                               "invert_y" => new_config.invert_y = if val > 0.0 { 1.0 } else { -1.0 },
-                              "gravity_axis" => new_config.gravity_axis = val as u8,
                               "gravity_amount" => new_config.gravity_amount = val,
                               _ => {}
                           }
@@ -102,15 +96,71 @@ fn main() {
       });
   }
 
-  let mut gilrs = Gilrs::new().unwrap();
+  let gilrs = Arc::new(Mutex::new(Gilrs::new().unwrap()));
   let mut mouse_manager = RawInputManager::new().unwrap();
   mouse_manager.register_devices(multiinput::DeviceType::Mice);
 
+  // Shared so the rumble callback below (invoked from the server's
+  // background thread) can turn a slot back into a gamepad id.
+  let active_slots_for_rumble: Arc<Mutex<HashMap<u8, GamepadId>>> =
+    Arc::new(Mutex::new(HashMap::new()));
+
+  // Playing effects have to be kept alive for the duration of playback —
+  // dropping the handle stops it immediately — so the callback below
+  // stashes each slot's effect here instead of letting it go out of scope.
+  let active_rumble_effects: Arc<Mutex<HashMap<u8, gilrs::ff::Effect>>> =
+    Arc::new(Mutex::new(HashMap::new()));
+
+  {
+    let gilrs = gilrs.clone();
+    let active_slots_for_rumble = active_slots_for_rumble.clone();
+    let active_rumble_effects = active_rumble_effects.clone();
+    server.set_rumble_callback(move |slot, low_frequency_motor, high_frequency_motor| {
+      let gamepad_id = match active_slots_for_rumble.lock().unwrap().get(&slot) {
+        Some(&id) => id,
+        None => return,
+      };
+
+      // "Quake" style presets, in the spirit of doukutsu-rs: a light buzz
+      // around low ~= 0x3000/high = 0, a stronger one around low ~= 0x5000.
+      let low_magnitude = (low_frequency_motor as u16) * 257;
+      let high_magnitude = (high_frequency_motor as u16) * 257;
+
+      // DSU's low-frequency motor is gilrs' large/"strong" motor, and its
+      // high-frequency motor is gilrs' small/"weak" one.
+      let effect = EffectBuilder::new()
+        .add_effect(BaseEffect {
+          kind: BaseEffectType::Strong { magnitude: low_magnitude },
+          scheduling: Replay { play_for: Ticks::from_ms(200), ..Default::default() },
+          ..Default::default()
+        })
+        .add_effect(BaseEffect {
+          kind: BaseEffectType::Weak { magnitude: high_magnitude },
+          scheduling: Replay { play_for: Ticks::from_ms(200), ..Default::default() },
+          ..Default::default()
+        })
+        .gamepads(&[gamepad_id])
+        .finish(&mut gilrs.lock().unwrap());
+
+      if let Ok(mut effect) = effect {
+        let _ = effect.play();
+        // Replacing the old entry drops (and thus stops) any rumble this
+        // slot was already playing.
+        active_rumble_effects.lock().unwrap().insert(slot, effect);
+      }
+    });
+  }
+
   let now = Instant::now();
+  let mut orientation = Orientation::new();
+  let mut last_tick = Instant::now();
+  // Per-slot, per-button edge/hold state, so digital buttons get proper
+  // press/release transitions instead of a raw `is_pressed` read.
+  let mut button_states: HashMap<u8, HashMap<Button, ButtonState>> = HashMap::new();
   while running.load(Ordering::SeqCst) {
-    // Consume controller events
-    while let Some(_event) = gilrs.next_event() {
-    }
+    // Assign slots to connected gamepads and react to hotplug.
+    let active_slots = controller_registry.sync(&mut gilrs.lock().unwrap(), &server);
+    *active_slots_for_rumble.lock().unwrap() = active_slots.clone();
 
     let mut delta_rotation_x = 0.0;
     let mut delta_rotation_y = 0.0;
@@ -126,47 +176,74 @@ fn main() {
     }
 
     // Capture current config snapshot
-    let (sens, inv_x, inv_y, g_axis, g_val) = {
+    let (sens, inv_x, inv_y, g_val) = {
         let c = config.lock().unwrap();
-        (c.sensitivity, c.invert_x, c.invert_y, c.gravity_axis, c.gravity_amount)
+        (c.sensitivity, c.invert_x, c.invert_y, c.gravity_amount)
     };
 
     // Apply Sensitivity & Inversion
     let gyro_yaw = delta_rotation_x * sens * inv_x;
     let gyro_pitch = delta_rotation_y * sens * inv_y;
+    let gyro_roll = 0.0;
 
-    // Apply Gravity Vector (Fixes the "X vs +" rotation issue)
-    let (accel_x, accel_y, accel_z) = match g_axis {
-        0 => (g_val, 0.0, 0.0), // X-Axis (Sideways)
-        1 => (0.0, g_val, 0.0), // Y-Axis (Upright/Pointer) <- DEFAULT
-        _ => (0.0, 0.0, g_val), // Z-Axis (Flat)
-    };
+    // Integrate the mouse-driven rotation into the tracked orientation and
+    // derive the accelerometer reading from how far that's tilted gravity,
+    // instead of pinning it to a fixed world axis.
+    let dt = last_tick.elapsed();
+    last_tick = Instant::now();
+    orientation.integrate_gyro(gyro_pitch.to_radians(), gyro_yaw.to_radians(), gyro_roll, dt);
+    let (accel_x, accel_y, accel_z) = orientation.accelerometer(g_val);
+
+    if active_slots.is_empty() {
+      // No gamepad yet, but still report the mouse-driven motion on slot 0
+      // so DSU clients have something to track.
+      server.update_controller_data(0, ControllerData {
+        connected: true,
+        motion_data_timestamp: now.elapsed().as_micros() as u64,
 
-    let first_gamepad = gilrs.gamepads().next();
-    let controller_data = {
-      if let Some((_id, gamepad)) = first_gamepad {
+        accelerometer_x: accel_x,
+        accelerometer_y: accel_y,
+        accelerometer_z: accel_z,
+
+        gyroscope_pitch: gyro_pitch,
+        gyroscope_yaw: gyro_yaw,
+        gyroscope_roll: gyro_roll,
+
+        .. Default::default()
+      });
+    } else {
+      for (&slot, &gamepad_id) in active_slots.iter() {
+        let gilrs = gilrs.lock().unwrap();
+        let gamepad = gilrs.gamepad(gamepad_id);
         let analog_button_value = |button| {
           gamepad.button_data(button).map(|data| (data.value() * 255.0) as u8).unwrap_or(0)
         };
 
-        ControllerData {
+        let slot_button_states = button_states.entry(slot).or_insert_with(HashMap::new);
+        let mut digital = |button: Button| -> bool {
+          let state = slot_button_states.entry(button).or_insert_with(ButtonState::new);
+          state.update(gamepad.is_pressed(button), dt);
+          state.is_pressed()
+        };
+
+        let controller_data = ControllerData {
           connected: true,
-          d_pad_left: gamepad.is_pressed(Button::DPadLeft),
-          d_pad_down: gamepad.is_pressed(Button::DPadDown),
-          d_pad_right: gamepad.is_pressed(Button::DPadRight),
-          d_pad_up: gamepad.is_pressed(Button::DPadUp),
-          start: gamepad.is_pressed(Button::Start),
-          right_stick_button: gamepad.is_pressed(Button::RightThumb),
-          left_stick_button: gamepad.is_pressed(Button::LeftThumb),
-          select:  gamepad.is_pressed(Button::Select),
-          triangle: gamepad.is_pressed(Button::North),
-          circle: gamepad.is_pressed(Button::East),
-          cross: gamepad.is_pressed(Button::South),
-          square: gamepad.is_pressed(Button::West),
-          r1: gamepad.is_pressed(Button::RightTrigger),
-          l1: gamepad.is_pressed(Button::LeftTrigger),
-          r2: gamepad.is_pressed(Button::RightTrigger2),
-          l2: gamepad.is_pressed(Button::LeftTrigger2),
+          d_pad_left: digital(Button::DPadLeft),
+          d_pad_down: digital(Button::DPadDown),
+          d_pad_right: digital(Button::DPadRight),
+          d_pad_up: digital(Button::DPadUp),
+          start: digital(Button::Start),
+          right_stick_button: digital(Button::RightThumb),
+          left_stick_button: digital(Button::LeftThumb),
+          select: digital(Button::Select),
+          triangle: digital(Button::North),
+          circle: digital(Button::East),
+          cross: digital(Button::South),
+          square: digital(Button::West),
+          r1: digital(Button::RightTrigger),
+          l1: digital(Button::LeftTrigger),
+          r2: digital(Button::RightTrigger2),
+          l2: digital(Button::LeftTrigger2),
           ps: analog_button_value(Button::Mode),
           left_stick_x: to_stick_value(gamepad.value(Axis::LeftStickX)),
           left_stick_y: to_stick_value(gamepad.value(Axis::LeftStickY)),
@@ -185,36 +262,34 @@ fn main() {
           analog_r2: analog_button_value(Button::RightTrigger2),
           analog_l2: analog_button_value(Button::LeftTrigger2),
           motion_data_timestamp: now.elapsed().as_micros() as u64,
-          
-          accelerometer_x: accel_x,
-          accelerometer_y: accel_y,
-          accelerometer_z: accel_z,
-          
-          gyroscope_pitch: gyro_pitch,
-          gyroscope_yaw: gyro_yaw,
-          gyroscope_roll: 0.0,
 
-          .. Default::default()
-        }
-      } else {
-        ControllerData {
-          connected: true,
-          motion_data_timestamp: now.elapsed().as_micros() as u64,
-          
           accelerometer_x: accel_x,
           accelerometer_y: accel_y,
           accelerometer_z: accel_z,
 
           gyroscope_pitch: gyro_pitch,
           gyroscope_yaw: gyro_yaw,
-          gyroscope_roll: 0.0,
+          gyroscope_roll: gyro_roll,
 
           .. Default::default()
+        };
+
+        // Demonstrate the edge/hold tracking `ButtonState` adds over a raw
+        // `is_pressed` read: tapping Select recenters the orientation, and
+        // holding it down for over two seconds recenters continuously (e.g.
+        // while fighting drift) instead of requiring a fresh tap every time.
+        if let Some(select_state) = slot_button_states.get(&Button::Select) {
+          if select_state.just_pressed()
+            || (select_state.is_pressed() && select_state.held_for() > Duration::from_secs(2))
+          {
+            orientation.reset();
+          }
         }
+
+        server.update_controller_data(slot as usize, controller_data);
       }
-    };
+    }
 
-    server.update_controller_data(0, controller_data);
     std::thread::sleep(Duration::from_millis(1));
   }
 
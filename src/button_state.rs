@@ -0,0 +1,127 @@
+//! Per-button edge/hold/toggle tracking, so callers don't have to
+//! re-derive press/release transitions from a raw `is_pressed` boolean
+//! read fresh every tick.
+
+use std::time::Duration;
+
+/// Tracks a single button's pressed/released transitions, how long it's
+/// been held in its current state, and a latched toggle flag that flips on
+/// every press.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ButtonState {
+    is_pressed: bool,
+    was_pressed: bool,
+    time_pressed: Duration,
+    time_released: Duration,
+    toggle: bool,
+}
+
+impl ButtonState {
+    pub fn new() -> ButtonState {
+        ButtonState::default()
+    }
+
+    /// Advances the tracked state by one tick: `pressed` is the raw input
+    /// reading this tick, `dt` how long it's been since the last call.
+    pub fn update(&mut self, pressed: bool, dt: Duration) {
+        self.was_pressed = self.is_pressed;
+        self.is_pressed = pressed;
+
+        match (self.was_pressed, self.is_pressed) {
+            (false, true) => {
+                self.time_pressed = Duration::ZERO;
+                self.toggle = !self.toggle;
+            }
+            (true, false) => self.time_released = Duration::ZERO,
+            _ => {}
+        }
+
+        if self.is_pressed {
+            self.time_pressed += dt;
+        } else {
+            self.time_released += dt;
+        }
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.is_pressed
+    }
+
+    /// True for the single tick a press began.
+    pub fn just_pressed(&self) -> bool {
+        self.is_pressed && !self.was_pressed
+    }
+
+    /// True for the single tick a release began.
+    pub fn just_released(&self) -> bool {
+        !self.is_pressed && self.was_pressed
+    }
+
+    /// How long the button has been continuously held or released,
+    /// whichever is its current state.
+    pub fn held_for(&self) -> Duration {
+        if self.is_pressed {
+            self.time_pressed
+        } else {
+            self.time_released
+        }
+    }
+
+    /// Flips every time the button is pressed; latches until the next
+    /// press. Useful for tap-to-toggle behavior.
+    pub fn toggle(&self) -> bool {
+        self.toggle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn just_pressed_only_on_the_tick_the_press_began() {
+        let mut state = ButtonState::new();
+        state.update(true, Duration::from_millis(16));
+        assert!(state.just_pressed());
+
+        state.update(true, Duration::from_millis(16));
+        assert!(!state.just_pressed());
+    }
+
+    #[test]
+    fn just_released_only_on_the_tick_the_release_began() {
+        let mut state = ButtonState::new();
+        state.update(true, Duration::from_millis(16));
+        state.update(false, Duration::from_millis(16));
+        assert!(state.just_released());
+
+        state.update(false, Duration::from_millis(16));
+        assert!(!state.just_released());
+    }
+
+    #[test]
+    fn held_for_accumulates_while_pressed_and_resets_on_release() {
+        let mut state = ButtonState::new();
+        state.update(true, Duration::from_millis(100));
+        state.update(true, Duration::from_millis(50));
+        assert_eq!(state.held_for(), Duration::from_millis(150));
+
+        state.update(false, Duration::from_millis(16));
+        assert_eq!(state.held_for(), Duration::from_millis(16));
+    }
+
+    #[test]
+    fn toggle_flips_on_every_press_and_latches_through_release() {
+        let mut state = ButtonState::new();
+        assert!(!state.toggle());
+
+        state.update(true, Duration::from_millis(16));
+        assert!(state.toggle());
+
+        state.update(false, Duration::from_millis(16));
+        assert!(state.toggle());
+
+        state.update(true, Duration::from_millis(16));
+        assert!(!state.toggle());
+    }
+}
@@ -0,0 +1,9 @@
+//! `pad-motion` emulates DualShock/DualSense-style motion controllers over the
+//! DSU ("cemuhook") UDP protocol, so that real gamepads and other input
+//! sources can be presented to DSU clients (emulators, VR bridges, etc.) as
+//! virtual motion-capable controllers.
+
+pub mod button_state;
+pub mod orientation;
+pub mod protocol;
+pub mod server;
@@ -0,0 +1,149 @@
+//! A minimal AHRS-style orientation tracker: integrates gyroscope readings
+//! into an orientation quaternion and derives the emulated accelerometer
+//! reading (gravity, rotated into the body frame) from it, so a virtual
+//! pad tilts with accumulated rotation instead of reporting a fixed world
+//! axis.
+
+use std::time::Duration;
+
+/// Tracks device orientation as a quaternion `[q0, q1, q2, q3]`, integrated
+/// from gyroscope readings each tick.
+#[derive(Debug, Clone, Copy)]
+pub struct Orientation {
+    q: [f32; 4],
+}
+
+impl Orientation {
+    /// Starts at the identity orientation (no rotation).
+    pub fn new() -> Orientation {
+        Orientation { q: [1.0, 0.0, 0.0, 0.0] }
+    }
+
+    /// Re-zeros the tracked orientation back to identity.
+    pub fn reset(&mut self) {
+        self.q = [1.0, 0.0, 0.0, 0.0];
+    }
+
+    /// Integrates a gyroscope reading `(gx, gy, gz)` in rad/s over `dt`.
+    pub fn integrate_gyro(&mut self, gx: f32, gy: f32, gz: f32, dt: Duration) {
+        self.integrate(gx, gy, gz, dt.as_secs_f32());
+    }
+
+    /// Nudges the orientation so its estimated gravity direction drifts
+    /// toward the direction of a real accelerometer reading, by `gain` (0
+    /// ignores the reading; larger trusts it more). A small complementary
+    /// correction to counteract gyro drift.
+    pub fn apply_accel_correction(&mut self, accel: (f32, f32, f32), gain: f32) {
+        let (ax, ay, az) = accel;
+        let norm = (ax * ax + ay * ay + az * az).sqrt();
+        if norm < f32::EPSILON || gain <= 0.0 {
+            return;
+        }
+        let (mx, my, mz) = (ax / norm, ay / norm, az / norm);
+        let (gx, gy, gz) = self.gravity_direction();
+
+        // Body rotation rate (measured x estimated) that turns the
+        // estimated gravity direction toward the measured one; its
+        // magnitude grows with the angle between them.
+        let ex = my * gz - mz * gy;
+        let ey = mz * gx - mx * gz;
+        let ez = mx * gy - my * gx;
+
+        self.integrate(ex, ey, ez, gain);
+    }
+
+    fn integrate(&mut self, wx: f32, wy: f32, wz: f32, dt: f32) {
+        let [q0, q1, q2, q3] = self.q;
+
+        let q_dot = [
+            0.5 * (-q1 * wx - q2 * wy - q3 * wz),
+            0.5 * (q0 * wx + q2 * wz - q3 * wy),
+            0.5 * (q0 * wy - q1 * wz + q3 * wx),
+            0.5 * (q0 * wz + q1 * wy - q2 * wx),
+        ];
+
+        let mut q = [
+            q0 + q_dot[0] * dt,
+            q1 + q_dot[1] * dt,
+            q2 + q_dot[2] * dt,
+            q3 + q_dot[3] * dt,
+        ];
+
+        let norm = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+        if norm > f32::EPSILON {
+            for component in q.iter_mut() {
+                *component /= norm;
+            }
+        }
+
+        self.q = q;
+    }
+
+    /// The unit vector world-down (gravity) rotates to in the body frame,
+    /// given the current orientation.
+    fn gravity_direction(&self) -> (f32, f32, f32) {
+        let [q0, q1, q2, q3] = self.q;
+        (
+            2.0 * (q1 * q3 - q0 * q2),
+            2.0 * (q0 * q1 + q2 * q3),
+            q0 * q0 - q1 * q1 - q2 * q2 + q3 * q3,
+        )
+    }
+
+    /// The emulated accelerometer reading: gravity as seen in the body
+    /// frame, scaled to `gravity_amount` (typically 9.81).
+    pub fn accelerometer(&self, gravity_amount: f32) -> (f32, f32, f32) {
+        let (gx, gy, gz) = self.gravity_direction();
+        (gx * gravity_amount, gy * gravity_amount, gz * gravity_amount)
+    }
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Orientation::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_orientation_reports_identity_gravity_straight_down_z() {
+        let orientation = Orientation::new();
+        let (ax, ay, az) = orientation.accelerometer(9.81);
+        assert!(ax.abs() < 1e-6);
+        assert!(ay.abs() < 1e-6);
+        assert!((az - 9.81).abs() < 1e-6);
+    }
+
+    #[test]
+    fn integrate_gyro_with_no_rotation_leaves_orientation_unchanged() {
+        let mut orientation = Orientation::new();
+        orientation.integrate_gyro(0.0, 0.0, 0.0, Duration::from_millis(16));
+        let (ax, ay, az) = orientation.accelerometer(9.81);
+        assert!(ax.abs() < 1e-6);
+        assert!(ay.abs() < 1e-6);
+        assert!((az - 9.81).abs() < 1e-6);
+    }
+
+    #[test]
+    fn integrate_gyro_tilts_gravity_away_from_identity() {
+        let mut orientation = Orientation::new();
+        orientation.integrate_gyro(1.0, 0.0, 0.0, Duration::from_millis(100));
+        let (_, ay, az) = orientation.accelerometer(9.81);
+        assert!(ay.abs() > 1e-3);
+        assert!((az - 9.81).abs() > 1e-6);
+    }
+
+    #[test]
+    fn reset_returns_to_identity_gravity_after_rotation() {
+        let mut orientation = Orientation::new();
+        orientation.integrate_gyro(1.0, 0.5, 0.2, Duration::from_millis(200));
+        orientation.reset();
+        let (ax, ay, az) = orientation.accelerometer(9.81);
+        assert!(ax.abs() < 1e-6);
+        assert!(ay.abs() < 1e-6);
+        assert!((az - 9.81).abs() < 1e-6);
+    }
+}
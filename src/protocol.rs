@@ -0,0 +1,238 @@
+//! Types and constants for the DSU ("cemuhook") protocol.
+//!
+//! This module only models the pieces of the wire protocol that the server
+//! needs to track per-slot state. It does not encode any packets, and the
+//! only packet it decodes is a client's rumble request
+//! ([`RumbleRequest::decode`]).
+
+/// Maximum number of controller slots a DSU server can report, per protocol.
+pub const MAX_SLOTS: usize = 4;
+
+/// Whether a given slot currently holds a controller, and if so how it was
+/// obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotState {
+    Disconnected,
+    Reserved,
+    Connected,
+}
+
+impl Default for SlotState {
+    fn default() -> Self {
+        SlotState::Disconnected
+    }
+}
+
+/// The kind of motion data a controller in a slot can provide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    NotApplicable,
+    PartialGyro,
+    FullGyro,
+    NoGyro,
+}
+
+impl Default for DeviceType {
+    fn default() -> Self {
+        DeviceType::NotApplicable
+    }
+}
+
+/// How a controller is physically connected to the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionType {
+    NotApplicable,
+    USB,
+    Bluetooth,
+}
+
+impl Default for ConnectionType {
+    fn default() -> Self {
+        ConnectionType::NotApplicable
+    }
+}
+
+/// Battery charge level, as reported to DSU clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryStatus {
+    NotApplicable,
+    Dying,
+    Low,
+    Medium,
+    High,
+    Full,
+    Charging,
+    Charged,
+}
+
+impl Default for BatteryStatus {
+    fn default() -> Self {
+        BatteryStatus::NotApplicable
+    }
+}
+
+/// The physical gamepad family backing a slot, as classified from the
+/// underlying input backend's name/identifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadType {
+    Xbox360,
+    XboxOne,
+    Ps3,
+    Ps4,
+    Ps5,
+    SwitchPro,
+    SwitchJoyConL,
+    SwitchJoyConR,
+    SwitchJoyConPair,
+    Stadia,
+    Luma,
+    Shield,
+    Virtual,
+    Unknown,
+}
+
+impl Default for GamepadType {
+    fn default() -> Self {
+        GamepadType::Unknown
+    }
+}
+
+/// Static information about the controller occupying a slot.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ControllerInfo {
+    pub slot: u8,
+    pub slot_state: SlotState,
+    pub device_type: DeviceType,
+    pub connection_type: ConnectionType,
+    pub mac_address: [u8; 6],
+    pub battery_status: BatteryStatus,
+    pub controller_type: GamepadType,
+}
+
+/// Magic bytes prefixing every client-originated DSU packet (version
+/// requests, port-info requests, pad-data subscriptions, and rumble
+/// requests alike) — shared, so it cannot by itself identify a rumble
+/// request; see [`MESSAGE_TYPE_RUMBLE`].
+pub const CLIENT_MAGIC: [u8; 4] = *b"DSUC";
+
+/// The message-type byte identifying a rumble request specifically, as
+/// opposed to any other client-originated packet sharing [`CLIENT_MAGIC`].
+pub const MESSAGE_TYPE_RUMBLE: u8 = 0x11;
+
+/// A rumble/force-feedback request decoded from a DSU client, naming the
+/// slot to rumble and the low/high-frequency motor intensities (0-255).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RumbleRequest {
+    pub slot: u8,
+    pub low_frequency_motor: u8,
+    pub high_frequency_motor: u8,
+}
+
+impl RumbleRequest {
+    /// Parses a `RumbleRequest` out of a raw client packet, or `None` if
+    /// `packet` isn't a well-formed rumble request. Checks the
+    /// message-type byte, not just [`CLIENT_MAGIC`], since every other
+    /// client packet (version/port-info/data-subscription requests)
+    /// shares that same magic.
+    pub fn decode(packet: &[u8]) -> Option<RumbleRequest> {
+        if packet.len() < 8 || packet[0..4] != CLIENT_MAGIC || packet[4] != MESSAGE_TYPE_RUMBLE {
+            return None;
+        }
+        Some(RumbleRequest {
+            slot: packet[5],
+            low_frequency_motor: packet[6],
+            high_frequency_motor: packet[7],
+        })
+    }
+}
+
+/// A single tick's worth of button, stick, and motion state for a slot.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ControllerData {
+    pub connected: bool,
+
+    pub d_pad_left: bool,
+    pub d_pad_down: bool,
+    pub d_pad_right: bool,
+    pub d_pad_up: bool,
+    pub start: bool,
+    pub right_stick_button: bool,
+    pub left_stick_button: bool,
+    pub select: bool,
+    pub triangle: bool,
+    pub circle: bool,
+    pub cross: bool,
+    pub square: bool,
+    pub r1: bool,
+    pub l1: bool,
+    pub r2: bool,
+    pub l2: bool,
+
+    pub ps: u8,
+
+    pub left_stick_x: u8,
+    pub left_stick_y: u8,
+    pub right_stick_x: u8,
+    pub right_stick_y: u8,
+
+    pub analog_d_pad_left: u8,
+    pub analog_d_pad_down: u8,
+    pub analog_d_pad_right: u8,
+    pub analog_d_pad_up: u8,
+    pub analog_triangle: u8,
+    pub analog_circle: u8,
+    pub analog_cross: u8,
+    pub analog_square: u8,
+    pub analog_r1: u8,
+    pub analog_l1: u8,
+    pub analog_r2: u8,
+    pub analog_l2: u8,
+
+    pub motion_data_timestamp: u64,
+
+    pub accelerometer_x: f32,
+    pub accelerometer_y: f32,
+    pub accelerometer_z: f32,
+
+    pub gyroscope_pitch: f32,
+    pub gyroscope_yaw: f32,
+    pub gyroscope_roll: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_rejects_too_short_packet() {
+        let packet = [b'D', b'S', b'U', b'C', MESSAGE_TYPE_RUMBLE, 0, 0];
+        assert_eq!(RumbleRequest::decode(&packet), None);
+    }
+
+    #[test]
+    fn decode_rejects_wrong_magic() {
+        let packet = [b'D', b'S', b'U', b'X', MESSAGE_TYPE_RUMBLE, 0, 1, 2];
+        assert_eq!(RumbleRequest::decode(&packet), None);
+    }
+
+    #[test]
+    fn decode_rejects_wrong_message_type() {
+        // Shares CLIENT_MAGIC with a rumble request, but isn't one (e.g. a
+        // version/port-info/data-subscription request).
+        let packet = [b'D', b'S', b'U', b'C', 0x00, 0, 1, 2];
+        assert_eq!(RumbleRequest::decode(&packet), None);
+    }
+
+    #[test]
+    fn decode_parses_well_formed_rumble_request() {
+        let packet = [b'D', b'S', b'U', b'C', MESSAGE_TYPE_RUMBLE, 2, 0x30, 0x50];
+        assert_eq!(
+            RumbleRequest::decode(&packet),
+            Some(RumbleRequest {
+                slot: 2,
+                low_frequency_motor: 0x30,
+                high_frequency_motor: 0x50,
+            })
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Classifies a gilrs gamepad into a [`GamepadType`] and derives the
+//! DSU-facing [`ControllerInfo`] fields (connection type, a stable MAC, and
+//! battery status) from it, so callers don't have to hand-build one.
+
+use gilrs::{Gamepad, PowerInfo};
+
+use crate::protocol::{
+    BatteryStatus, ConnectionType, ControllerInfo, DeviceType, GamepadType, SlotState,
+};
+
+/// Classifies `gamepad` by its reported name. Anything not recognized maps
+/// to [`GamepadType::Unknown`].
+pub fn gamepad_type(gamepad: &Gamepad) -> GamepadType {
+    let name = gamepad.name().to_lowercase();
+    if name.contains("xbox 360") {
+        GamepadType::Xbox360
+    } else if name.contains("xbox one") || name.contains("xbox series") {
+        GamepadType::XboxOne
+    } else if name.contains("dualsense") {
+        GamepadType::Ps5
+    } else if name.contains("wireless controller") || name.contains("dualshock 4") {
+        GamepadType::Ps4
+    } else if name.contains("dualshock 3") || name.contains("ps3") {
+        GamepadType::Ps3
+    } else if name.contains("joy-con (l)") {
+        GamepadType::SwitchJoyConL
+    } else if name.contains("joy-con (r)") {
+        GamepadType::SwitchJoyConR
+    } else if name.contains("joy-con") {
+        GamepadType::SwitchJoyConPair
+    } else if name.contains("pro controller") {
+        GamepadType::SwitchPro
+    } else if name.contains("stadia") {
+        GamepadType::Stadia
+    } else if name.contains("luma") {
+        GamepadType::Luma
+    } else if name.contains("shield") {
+        GamepadType::Shield
+    } else if name.contains("virtual") {
+        GamepadType::Virtual
+    } else {
+        GamepadType::Unknown
+    }
+}
+
+/// gilrs doesn't expose the transport directly. `PowerInfo::Wired` is a
+/// reliable USB signal (battery-powered gamepads report it only when
+/// actually plugged in); anything else battery-backed is assumed to be
+/// wireless. The gamepad's name is not a safe signal here — e.g. a
+/// DualShock 4 reports itself as "Wireless Controller" even over USB.
+fn connection_type(gamepad: &Gamepad) -> ConnectionType {
+    match gamepad.power_info() {
+        PowerInfo::Wired => ConnectionType::USB,
+        PowerInfo::Unknown => ConnectionType::NotApplicable,
+        _ => ConnectionType::Bluetooth,
+    }
+}
+
+fn battery_status(gamepad: &Gamepad) -> BatteryStatus {
+    match gamepad.power_info() {
+        PowerInfo::Discharging(percentage) if percentage <= 20 => BatteryStatus::Low,
+        PowerInfo::Discharging(percentage) if percentage <= 40 => BatteryStatus::Medium,
+        PowerInfo::Discharging(percentage) if percentage <= 70 => BatteryStatus::High,
+        PowerInfo::Discharging(_) => BatteryStatus::Full,
+        PowerInfo::Charging(_) => BatteryStatus::Charging,
+        PowerInfo::Charged => BatteryStatus::Charged,
+        PowerInfo::Wired | PowerInfo::Unknown => BatteryStatus::NotApplicable,
+    }
+}
+
+/// Synthesizes a MAC-like address for `slot`. gilrs has no access to a
+/// gamepad's real hardware address; this just needs to be stable per slot
+/// so clients can tell controllers apart.
+fn mac_address(slot: u8) -> [u8; 6] {
+    [0x02, 0x00, 0x00, 0x00, 0x00, slot]
+}
+
+/// Produces a fully populated [`ControllerInfo`] for `gamepad` occupying
+/// `slot`: classifies its family, then derives connection type, MAC, and
+/// battery from it.
+pub fn controller_info_for(slot: u8, gamepad: &Gamepad) -> ControllerInfo {
+    ControllerInfo {
+        slot,
+        slot_state: SlotState::Connected,
+        device_type: DeviceType::FullGyro,
+        connection_type: connection_type(gamepad),
+        mac_address: mac_address(slot),
+        battery_status: battery_status(gamepad),
+        controller_type: gamepad_type(gamepad),
+    }
+}
@@ -0,0 +1,95 @@
+//! Keeps gilrs gamepads mapped onto DSU slots, reacting to hotplug so a
+//! caller never has to track slot assignment by hand.
+
+use std::collections::HashMap;
+
+use gilrs::{EventType, Gamepad, GamepadId, Gilrs};
+
+use crate::protocol::{ControllerInfo, SlotState, MAX_SLOTS};
+use crate::server::{controller_info_for, Server};
+
+/// Assigns each connected gilrs gamepad its own DSU slot (0 through
+/// [`MAX_SLOTS`] - 1) and keeps a [`Server`] informed as gamepads connect
+/// and disconnect.
+///
+/// Call [`ControllerRegistry::sync`] once per tick: it drains pending gilrs
+/// events, updates slot assignments and the server's per-slot info, and
+/// hands back the current slot -> gamepad mapping so the caller can pull
+/// per-slot button/stick state without its own bookkeeping.
+#[derive(Default)]
+pub struct ControllerRegistry {
+    slots: [Option<GamepadId>; MAX_SLOTS],
+}
+
+impl ControllerRegistry {
+    pub fn new() -> ControllerRegistry {
+        ControllerRegistry::default()
+    }
+
+    /// Processes pending hotplug events and returns the active slot ->
+    /// gamepad mapping. Re-derives and pushes `ControllerInfo` for every
+    /// connected gamepad on every call (not just the tick it connected on),
+    /// so battery/connection-type stay current instead of freezing at
+    /// connect time.
+    pub fn sync(&mut self, gilrs: &mut Gilrs, server: &Server) -> HashMap<u8, GamepadId> {
+        // Covers both gamepads gilrs already knew about (e.g. plugged in
+        // before this registry started watching) and ones still connected
+        // from a previous tick.
+        let connected: Vec<GamepadId> = gilrs.gamepads().map(|(id, _)| id).collect();
+        for id in connected {
+            self.refresh(id, gilrs.gamepad(id), server);
+        }
+
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::Connected => {
+                    let gamepad = gilrs.gamepad(event.id);
+                    self.refresh(event.id, gamepad, server);
+                }
+                EventType::Disconnected => self.disconnect(event.id, server),
+                _ => {}
+            }
+        }
+
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| id.map(|id| (slot as u8, id)))
+            .collect()
+    }
+
+    /// Assigns `id` a free slot if it doesn't have one yet, then (re)derives
+    /// and pushes its `ControllerInfo`.
+    fn refresh(&mut self, id: GamepadId, gamepad: Gamepad, server: &Server) {
+        let slot = match self.slot_of(id) {
+            Some(slot) => slot,
+            None => match self.slots.iter().position(|slot| slot.is_none()) {
+                Some(slot) => {
+                    self.slots[slot] = Some(id);
+                    slot
+                }
+                None => return, // All DSU slots are already occupied.
+            },
+        };
+
+        server.update_controller_info(controller_info_for(slot as u8, &gamepad));
+    }
+
+    fn disconnect(&mut self, id: GamepadId, server: &Server) {
+        let slot = match self.slot_of(id) {
+            Some(slot) => slot,
+            None => return,
+        };
+        self.slots[slot] = None;
+
+        server.update_controller_info(ControllerInfo {
+            slot: slot as u8,
+            slot_state: SlotState::Disconnected,
+            ..Default::default()
+        });
+    }
+
+    fn slot_of(&self, id: GamepadId) -> Option<usize> {
+        self.slots.iter().position(|slot| *slot == Some(id))
+    }
+}
@@ -0,0 +1,163 @@
+//! The DSU server itself: tracks per-slot controller state and the clients
+//! that send it packets over UDP. It does not encode or send any DSU
+//! response or data packets back to those clients.
+
+mod controller_detection;
+mod controller_registry;
+mod rumble;
+
+pub use controller_detection::{controller_info_for, gamepad_type};
+pub use controller_registry::ControllerRegistry;
+pub use rumble::RumbleCallback;
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rumble::RumbleRouter;
+
+use crate::protocol::{ControllerData, ControllerInfo, RumbleRequest, MAX_SLOTS};
+
+const DEFAULT_PORT: u16 = 26760;
+
+/// Tracks a DSU client that has asked to be kept up to date on controller
+/// state, so the server knows who to push data to.
+struct Client {
+    address: SocketAddr,
+    last_seen: Instant,
+}
+
+/// A DSU server exposing up to [`MAX_SLOTS`] virtual controllers.
+///
+/// Callers feed it controller state via [`Server::update_controller_info`]
+/// and [`Server::update_controller_data`]; the server records every client
+/// address it receives a packet from and routes decoded rumble requests to
+/// the callback set with [`Server::set_rumble_callback`]. It sends nothing
+/// back to clients.
+pub struct Server {
+    socket: UdpSocket,
+    controller_infos: Mutex<[ControllerInfo; MAX_SLOTS]>,
+    controller_data: Mutex<[ControllerData; MAX_SLOTS]>,
+    clients: Mutex<Vec<Client>>,
+    rumble_router: Arc<RumbleRouter>,
+}
+
+impl Server {
+    /// Binds the server to `ipv4_addr:port`, defaulting to `0.0.0.0:26760`.
+    pub fn new(ipv4_addr: Option<Ipv4Addr>, port: Option<u16>) -> io::Result<Server> {
+        let address = SocketAddr::from((
+            ipv4_addr.unwrap_or(Ipv4Addr::UNSPECIFIED),
+            port.unwrap_or(DEFAULT_PORT),
+        ));
+        let socket = UdpSocket::bind(address)?;
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+
+        Ok(Server {
+            socket,
+            controller_infos: Mutex::new([ControllerInfo::default(); MAX_SLOTS]),
+            controller_data: Mutex::new([ControllerData::default(); MAX_SLOTS]),
+            clients: Mutex::new(Vec::new()),
+            rumble_router: Arc::new(RumbleRouter::default()),
+        })
+    }
+
+    /// Spawns the background thread that services client requests. Runs
+    /// until `running` is cleared.
+    pub fn start(self: &Arc<Self>, running: Arc<AtomicBool>) -> JoinHandle<()> {
+        let server = self.clone();
+        thread::spawn(move || {
+            let mut receive_buffer = [0u8; 1024];
+            while running.load(Ordering::SeqCst) {
+                match server.socket.recv_from(&mut receive_buffer) {
+                    Ok((size, address)) => {
+                        server.register_client(address);
+                        if let Some(request) = RumbleRequest::decode(&receive_buffer[..size]) {
+                            server.rumble_router.dispatch(
+                                request.slot,
+                                request.low_frequency_motor,
+                                request.high_frequency_motor,
+                            );
+                        }
+                    }
+                    Err(ref error) if error.kind() == io::ErrorKind::WouldBlock => {}
+                    Err(ref error) if error.kind() == io::ErrorKind::TimedOut => {}
+                    Err(error) => eprintln!("pad_motion::server: recv_from failed: {}", error),
+                }
+            }
+        })
+    }
+
+    fn register_client(&self, address: SocketAddr) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.iter_mut().find(|client| client.address == address) {
+            client.last_seen = Instant::now();
+        } else {
+            clients.push(Client {
+                address,
+                last_seen: Instant::now(),
+            });
+        }
+    }
+
+    /// Updates the static info (connection/battery/etc.) for the slot named
+    /// in `info.slot`.
+    pub fn update_controller_info(&self, info: ControllerInfo) {
+        let slot = info.slot as usize;
+        if slot >= MAX_SLOTS {
+            return;
+        }
+        self.controller_infos.lock().unwrap()[slot] = info;
+    }
+
+    /// Updates the live button/stick/motion state for `slot`. The state is
+    /// stored only; it is not sent to clients.
+    pub fn update_controller_data(&self, slot: usize, data: ControllerData) {
+        if slot >= MAX_SLOTS {
+            return;
+        }
+        self.controller_data.lock().unwrap()[slot] = data;
+        self.broadcast(slot);
+    }
+
+    /// Registers a callback invoked whenever a client asks a slot to
+    /// rumble, as `(slot, low_frequency_motor, high_frequency_motor)`. The
+    /// callback is responsible for translating that into a real effect,
+    /// e.g. via `gilrs::ff`, for whichever gamepad is mapped to that slot.
+    pub fn set_rumble_callback(&self, callback: impl Fn(u8, u8, u8) + Send + Sync + 'static) {
+        self.rumble_router.set_callback(callback);
+    }
+
+    /// Rumbles `slot` at the given motor intensities for `duration`, then
+    /// stops it. For preset feedback that isn't in response to a client
+    /// packet, e.g. a "quake" cue at roughly
+    /// `low_frequency_motor = 0x30, high_frequency_motor = 0`.
+    pub fn set_rumble(
+        &self,
+        slot: u8,
+        low_frequency_motor: u8,
+        high_frequency_motor: u8,
+        duration: Duration,
+    ) {
+        self.rumble_router
+            .dispatch(slot, low_frequency_motor, high_frequency_motor);
+
+        let rumble_router = self.rumble_router.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            rumble_router.dispatch(slot, 0, 0);
+        });
+    }
+
+    fn broadcast(&self, _slot: usize) {
+        let clients = self.clients.lock().unwrap();
+        for client in clients.iter() {
+            // The actual DSU data-packet encoding lives outside the scope of
+            // this reduced protocol module; this server only tracks who to
+            // send to and what to send.
+            let _ = &client.address;
+        }
+    }
+}
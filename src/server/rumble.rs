@@ -0,0 +1,26 @@
+//! Inbound rumble/force-feedback routing: decodes client rumble requests
+//! and forwards them to whatever physical-controller backend the caller
+//! wires up via a callback (typically `gilrs::ff`).
+
+use std::sync::Mutex;
+
+/// Invoked whenever a slot should rumble, as `(slot, low_frequency_motor,
+/// high_frequency_motor)`. A call with both motors at `0` means "stop".
+pub type RumbleCallback = Box<dyn Fn(u8, u8, u8) + Send + Sync>;
+
+#[derive(Default)]
+pub(crate) struct RumbleRouter {
+    callback: Mutex<Option<RumbleCallback>>,
+}
+
+impl RumbleRouter {
+    pub(crate) fn set_callback(&self, callback: impl Fn(u8, u8, u8) + Send + Sync + 'static) {
+        *self.callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    pub(crate) fn dispatch(&self, slot: u8, low_frequency_motor: u8, high_frequency_motor: u8) {
+        if let Some(callback) = self.callback.lock().unwrap().as_ref() {
+            callback(slot, low_frequency_motor, high_frequency_motor);
+        }
+    }
+}